@@ -0,0 +1,43 @@
+// Copyright 2015 Bruno Medeiros
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use ::util::core::*;
+use ::source_model::{ SourceMessage, SourceRange };
+use ::parse_describe::StructureElementKind;
+
+/// Abstracts over the different serializations of a parse analysis, so
+/// that `parse_analysis_contents` and `StructureVisitor` don't need to
+/// care whether they are writing the legacy `RUST_PARSE_DESCRIBE` token
+/// stream or a JSON document.
+pub trait AnalysisWriter {
+
+	/// Writes whatever wrapper/header the format needs before anything else.
+	fn write_preamble(&mut self) -> Void;
+
+	/// Writes the collected messages.
+	fn write_messages(&mut self, messages : &[SourceMessage]) -> Void;
+
+	/// Opens a structure element (as produced by `StructureVisitor`). Any
+	/// elements written before the matching `end_element` are nested as
+	/// this element's children (e.g. fields under a struct, methods under
+	/// an impl).
+	fn begin_element(&mut self, kind : &StructureElementKind, range : &SourceRange, name : &str) -> Void;
+
+	/// Closes the structure element opened by the last unmatched `begin_element`.
+	fn end_element(&mut self) -> Void;
+
+	/// Writes whatever wrapper/footer the format needs after everything else.
+	fn write_postamble(&mut self) -> Void;
+
+}
@@ -0,0 +1,123 @@
+// Copyright 2015 Bruno Medeiros
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+extern crate rainicorn;
+extern crate structopt;
+#[macro_use]
+extern crate structopt_derive;
+
+use structopt::StructOpt;
+
+use rainicorn::parse_describe::{ parse_analysis, parse_analysis_path, OutputFormat };
+use rainicorn::locale::LangId;
+use rainicorn::util::string::StdoutWrite;
+
+use std::fs::File;
+use std::io;
+use std::io::{ Read, Write };
+use std::path::PathBuf;
+use std::process;
+
+#[derive(StructOpt)]
+#[structopt(name = "rainicorn", about = "Describes the structure and diagnostics of Rust source code.")]
+struct Opt {
+	#[structopt(long = "json", help = "Emit JSON instead of the legacy RUST_PARSE_DESCRIBE token stream")]
+	json : bool,
+
+	#[structopt(long = "locale", help = "Preferred locale for diagnostic messages (e.g. de-DE); may be repeated, most preferred first")]
+	locale : Vec<String>,
+
+	#[structopt(long = "crate-root", help = "Analyze the whole crate rooted at this directory instead of single files", parse(from_os_str))]
+	crate_root : Option<PathBuf>,
+
+	#[structopt(help = "Source file(s) to analyze; with more than one, runs in batch mode. Reads stdin if none are given.", parse(from_os_str))]
+	paths : Vec<PathBuf>,
+}
+
+fn main() {
+	let opt = Opt::from_args();
+
+	let format = if opt.json { OutputFormat::Json } else { OutputFormat::Legacy };
+	let locales : Vec<LangId> = opt.locale.iter().map(|lang| LangId(lang.clone())).collect();
+
+	if let Some(root) = opt.crate_root {
+		runCrateRoot(&root, format, &locales);
+		return;
+	}
+
+	if opt.paths.is_empty() {
+		runStdin(format, &locales);
+		return;
+	}
+
+	if opt.paths.len() == 1 {
+		runFile(&opt.paths[0], format, &locales);
+	} else {
+		runBatch(&opt.paths, format, &locales);
+	}
+}
+
+fn runStdin(format : OutputFormat, locales : &[LangId]) {
+	let mut source = String::new();
+	if let Err(err) = io::stdin().read_to_string(&mut source) {
+		writeln!(io::stderr(), "error: failed to read stdin: {}", err).ok();
+		process::exit(1);
+	}
+
+	writeAnalysis(&source, format, locales);
+}
+
+fn runFile(path : &PathBuf, format : OutputFormat, locales : &[LangId]) {
+	match readFileToString(path) {
+		Ok(source) => writeAnalysis(&source, format, locales),
+		Err(err) => {
+			writeln!(io::stderr(), "error: {}: {}", path.display(), err).ok();
+			process::exit(1);
+		}
+	}
+}
+
+fn runBatch(paths : &[PathBuf], format : OutputFormat, locales : &[LangId]) {
+	for path in paths {
+		println!("FILE {}", path.display());
+		match readFileToString(path) {
+			Ok(source) => writeAnalysis(&source, format, locales),
+			Err(err) => {
+				writeln!(io::stderr(), "error: {}: {}", path.display(), err).ok();
+				continue;
+			}
+		}
+	}
+}
+
+fn runCrateRoot(root : &PathBuf, format : OutputFormat, locales : &[LangId]) {
+	let result = parse_analysis_path(root, format, locales, StdoutWrite(io::stdout()));
+	result.ok();
+	println!("");
+	io::stdout().flush().ok();
+}
+
+fn writeAnalysis(source : &str, format : OutputFormat, locales : &[LangId]) {
+	let result = parse_analysis(source, format, locales, StdoutWrite(io::stdout()));
+	result.ok();
+	println!("");
+	io::stdout().flush().ok();
+}
+
+fn readFileToString(path : &PathBuf) -> io::Result<String> {
+	let mut file = try!(File::open(path));
+	let mut source = String::new();
+	try!(file.read_to_string(&mut source));
+	Ok(source)
+}
@@ -0,0 +1,82 @@
+// Copyright 2015 Bruno Medeiros
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use ::syntex_syntax::syntax::codemap;
+
+use std::fs::File;
+use std::io;
+use std::io::Read;
+use std::path::{ Path, PathBuf };
+
+/// A `FileLoader` that resolves `mod name;` declarations against the real
+/// filesystem, rooted at a given crate directory, so that a whole crate
+/// (not just a single in-memory buffer) can be analyzed.
+///
+/// The parser itself is responsible for computing the candidate paths for
+/// a `mod name;` (`<dir>/name.rs` and `<dir>/name/mod.rs`); this loader
+/// only needs to check whether such a path exists on disk and read it.
+pub struct FilesystemFileLoader {
+	root : PathBuf,
+}
+
+impl FilesystemFileLoader {
+
+	pub fn new(root : &Path) -> FilesystemFileLoader {
+		FilesystemFileLoader { root : root.to_path_buf() }
+	}
+
+	fn resolve(&self, path : &Path) -> PathBuf {
+		if path.is_absolute() {
+			path.to_path_buf()
+		} else {
+			self.root.join(path)
+		}
+	}
+
+}
+
+impl codemap::FileLoader for FilesystemFileLoader {
+
+	fn file_exists(&self, path : &Path) -> bool {
+		self.resolve(path).is_file()
+	}
+
+	fn read_file(&self, path : &Path) -> io::Result<String> {
+		let mut file = try!(File::open(self.resolve(path)));
+		let mut contents = String::new();
+		try!(file.read_to_string(&mut contents));
+		Ok(contents)
+	}
+
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use std::path::Path;
+
+	#[test]
+	fn resolve_joins_relative_paths_under_root() {
+		let loader = FilesystemFileLoader::new(Path::new("/crate/root"));
+		assert_eq!(loader.resolve(Path::new("foo.rs")), Path::new("/crate/root/foo.rs"));
+	}
+
+	#[test]
+	fn resolve_does_not_rejoin_paths_that_are_already_absolute() {
+		// `mod` paths computed from an absolute entry file come back already
+		// rooted, and are used exactly as given.
+		let loader = FilesystemFileLoader::new(Path::new("/crate/root"));
+		assert_eq!(loader.resolve(Path::new("/crate/root/foo.rs")), Path::new("/crate/root/foo.rs"));
+	}
+}
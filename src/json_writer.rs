@@ -0,0 +1,214 @@
+// Copyright 2015 Bruno Medeiros
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use ::util::core::*;
+use ::source_model::{ SourceMessage, SourceRange, StatusLevel };
+use ::parse_describe::StructureElementKind;
+use ::analysis_writer::AnalysisWriter;
+
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::fmt;
+
+/// Serializes a parse analysis as a single JSON object
+/// `{ "messages": [...], "structure": [...] }`, for editor plugins that
+/// would rather not parse the bespoke `RUST_PARSE_DESCRIBE` grammar.
+pub struct JsonWriter {
+	pub out : Rc<RefCell<fmt::Write>>,
+	/// One entry per currently-open array (the top-level `"structure"`
+	/// array, plus one per open element's `"children"` array), tracking
+	/// whether a sibling has already been written at that depth.
+	structureStack : Vec<bool>,
+}
+
+impl JsonWriter {
+
+	pub fn new(out : Rc<RefCell<fmt::Write>>) -> JsonWriter {
+		JsonWriter { out : out, structureStack : vec![] }
+	}
+
+	fn writeJsonString(&self, str : &str) -> Void {
+		let mut out = self.out.borrow_mut();
+
+		try!(out.write_str("\""));
+		for ch in str.chars() {
+			match ch {
+				'"' => try!(out.write_str("\\\"")),
+				'\\' => try!(out.write_str("\\\\")),
+				'\n' => try!(out.write_str("\\n")),
+				'\r' => try!(out.write_str("\\r")),
+				'\t' => try!(out.write_str("\\t")),
+				ch if (ch as u32) < 0x20 => try!(out.write_fmt(format_args!("\\u{:04x}", ch as u32))),
+				_ => try!(out.write_char(ch)),
+			}
+		}
+		try!(out.write_str("\""));
+
+		Ok(())
+	}
+
+	fn writeSourceRange(&self, range : &SourceRange) -> Void {
+		try!(self.out.borrow_mut().write_str("{\"file\":"));
+		try!(self.writeJsonString(&range.file));
+		try!(self.out.borrow_mut().write_fmt(format_args!(
+			",\"startLine\":{},\"startCol\":{},\"endLine\":{},\"endCol\":{}}}",
+			range.start_pos.line, range.start_pos.col.0,
+			range.end_pos.line, range.end_pos.col.0,
+		)));
+
+		Ok(())
+	}
+
+	fn writeOptSourceRange(&self, range : &Option<SourceRange>) -> Void {
+		match range {
+			&None => try!(self.out.borrow_mut().write_str("null")),
+			&Some(ref r) => try!(self.writeSourceRange(r)),
+		}
+
+		Ok(())
+	}
+
+	fn statusLevelString(lvl : &StatusLevel) -> &'static str {
+		match *lvl {
+			StatusLevel::OK => "OK",
+			StatusLevel::WARNING => "WARNING",
+			StatusLevel::ERROR => "ERROR",
+		}
+	}
+
+	/// Writes a `,` if a sibling was already written at the current array
+	/// depth, then marks that depth as having a sibling.
+	fn writeSiblingSeparator(&mut self) -> Void {
+		if let Some(hasSibling) = self.structureStack.last_mut() {
+			if *hasSibling {
+				try!(self.out.borrow_mut().write_str(","));
+			}
+			*hasSibling = true;
+		}
+		Ok(())
+	}
+
+	fn write_message(&self, msg : &SourceMessage) -> Void {
+		try!(self.out.borrow_mut().write_str("{\"level\":\""));
+		try!(self.out.borrow_mut().write_str(JsonWriter::statusLevelString(&msg.status_level)));
+		try!(self.out.borrow_mut().write_str("\",\"range\":"));
+		try!(self.writeOptSourceRange(&msg.sourcerange));
+
+		try!(self.out.borrow_mut().write_str(",\"message\":"));
+		try!(self.writeJsonString(&msg.message));
+
+		try!(self.out.borrow_mut().write_str(",\"code\":"));
+		match msg.code {
+			None => try!(self.out.borrow_mut().write_str("null")),
+			Some(ref code) => try!(self.writeJsonString(code)),
+		}
+
+		try!(self.out.borrow_mut().write_str(",\"notes\":["));
+		for (ix, note) in msg.notes.iter().enumerate() {
+			if ix > 0 { try!(self.out.borrow_mut().write_str(",")); }
+			try!(self.writeJsonString(note));
+		}
+		try!(self.out.borrow_mut().write_str("],\"related\":["));
+		for (ix, rel) in msg.related.iter().enumerate() {
+			if ix > 0 { try!(self.out.borrow_mut().write_str(",")); }
+			try!(self.out.borrow_mut().write_str("{\"range\":"));
+			try!(self.writeSourceRange(&rel.sourcerange));
+			try!(self.out.borrow_mut().write_str(",\"label\":"));
+			try!(self.writeJsonString(&rel.label));
+			try!(self.out.borrow_mut().write_str("}"));
+		}
+		try!(self.out.borrow_mut().write_str("]}"));
+
+		Ok(())
+	}
+
+}
+
+impl AnalysisWriter for JsonWriter {
+
+	fn write_preamble(&mut self) -> Void {
+		try!(self.out.borrow_mut().write_str("{"));
+		Ok(())
+	}
+
+	fn write_messages(&mut self, messages : &[SourceMessage]) -> Void {
+		try!(self.out.borrow_mut().write_str("\"messages\":["));
+		for (ix, msg) in messages.iter().enumerate() {
+			if ix > 0 { try!(self.out.borrow_mut().write_str(",")); }
+			try!(self.write_message(msg));
+		}
+		try!(self.out.borrow_mut().write_str("],\"structure\":["));
+		self.structureStack.push(false);
+
+		Ok(())
+	}
+
+	fn begin_element(&mut self, kind : &StructureElementKind, range : &SourceRange, name : &str) -> Void {
+		try!(self.writeSiblingSeparator());
+
+		let mut kindStr = String::new();
+		try!(kind.writeString(&mut kindStr));
+
+		try!(self.out.borrow_mut().write_str("{\"kind\":\""));
+		try!(self.out.borrow_mut().write_str(&kindStr));
+		try!(self.out.borrow_mut().write_str("\",\"range\":"));
+		try!(self.writeSourceRange(range));
+		try!(self.out.borrow_mut().write_str(",\"name\":"));
+		try!(self.writeJsonString(name));
+		try!(self.out.borrow_mut().write_str(",\"children\":["));
+
+		self.structureStack.push(false);
+
+		Ok(())
+	}
+
+	fn end_element(&mut self) -> Void {
+		self.structureStack.pop();
+		try!(self.out.borrow_mut().write_str("]}"));
+		Ok(())
+	}
+
+	fn write_postamble(&mut self) -> Void {
+		self.structureStack.pop();
+		try!(self.out.borrow_mut().write_str("]}"));
+		Ok(())
+	}
+
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use std::cell::RefCell;
+	use std::rc::Rc;
+
+	fn writeJsonString(str : &str) -> String {
+		let buffer = Rc::new(RefCell::new(String::new()));
+		let out : Rc<RefCell<fmt::Write>> = buffer.clone();
+		JsonWriter::new(out).writeJsonString(str).ok();
+		let result = buffer.borrow().clone();
+		result
+	}
+
+	#[test]
+	fn escapes_quote_and_backslash() {
+		assert_eq!(writeJsonString("a\"b\\c"), "\"a\\\"b\\\\c\"");
+	}
+
+	#[test]
+	fn escapes_all_control_characters_not_just_newline() {
+		assert_eq!(writeJsonString("a\nb\tc\rd"), "\"a\\nb\\tc\\rd\"");
+		assert_eq!(writeJsonString("\u{0001}"), "\"\\u0001\"");
+	}
+}
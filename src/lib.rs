@@ -0,0 +1,25 @@
+// Copyright 2015 Bruno Medeiros
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+extern crate syntex_syntax;
+
+pub mod util;
+pub mod source_model;
+pub mod locale;
+pub mod analysis_writer;
+pub mod token_writer;
+pub mod json_writer;
+pub mod file_loader;
+pub mod structure_visitor;
+pub mod parse_describe;
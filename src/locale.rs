@@ -0,0 +1,87 @@
+// Copyright 2015 Bruno Medeiros
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashMap;
+
+/// A locale identifier, e.g. `"de-DE"` or `"en-US"`.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct LangId(pub String);
+
+/// A set of localized message templates for a single locale. Templates
+/// may reference named arguments with `{name}` placeholders, in the
+/// style of Fluent/gettext message catalogs.
+pub struct MessageCatalog {
+	pub lang : LangId,
+	templates : HashMap<String, String>,
+}
+
+impl MessageCatalog {
+
+	pub fn new(lang : LangId) -> MessageCatalog {
+		MessageCatalog { lang : lang, templates : HashMap::new() }
+	}
+
+	pub fn insert(&mut self, messageId : &str, template : &str) {
+		self.templates.insert(String::from(messageId), String::from(template));
+	}
+
+	fn lookup(&self, messageId : &str) -> Option<&str> {
+		self.templates.get(messageId).map(|template| template.as_str())
+	}
+
+}
+
+fn interpolate(template : &str, args : &[(String, String)]) -> String {
+	let mut result = String::from(template);
+	for &(ref name, ref value) in args {
+		result = result.replace(&format!("{{{}}}", name), value);
+	}
+	result
+}
+
+/// Resolves a diagnostic's display text: tries each catalog in the
+/// fallback chain in order for `messageId`, interpolating `args` into
+/// the first template found; if no catalog in the chain has an entry
+/// for `messageId` (or the diagnostic has no stable id at all), returns
+/// `fallback` unchanged, so nothing is ever lost.
+pub fn resolve_message(catalogs : &[MessageCatalog], messageId : &Option<String>, args : &[(String, String)], fallback : &str) -> String {
+	if let &Some(ref messageId) = messageId {
+		for catalog in catalogs {
+			if let Some(template) = catalog.lookup(messageId) {
+				return interpolate(template, args);
+			}
+		}
+	}
+
+	String::from(fallback)
+}
+
+/// Looks up the catalogs for a fallback chain of locales, in order,
+/// skipping any locale this build doesn't have a catalog for.
+pub fn catalogs_for_locales(locales : &[LangId]) -> Vec<MessageCatalog> {
+	locales.iter().filter_map(|lang| built_in_catalog(lang)).collect()
+}
+
+/// The catalogs bundled with Rainicorn itself. Real deployments would
+/// instead load these from resource files alongside the binary.
+fn built_in_catalog(lang : &LangId) -> Option<MessageCatalog> {
+	match &lang.0[..] {
+		"de-DE" => {
+			let mut catalog = MessageCatalog::new(lang.clone());
+			catalog.insert("E0308", "Typen stimmen nicht überein: erwartet „{expected}“, gefunden „{found}“");
+			Some(catalog)
+		}
+		_ => None,
+	}
+}
@@ -35,7 +35,6 @@ use std::io::Write;
 /* ----------------- Model ----------------- */
 
 pub enum StructureElementKind {
-	Var,
 	Function,
 	Struct,
 	Impl,
@@ -46,6 +45,12 @@ pub enum StructureElementKind {
 	Mod,
 	Use,
 	TypeAlias,
+	Const,
+	Static,
+	Macro,
+	Field,
+	Parameter,
+	Method,
 }
 
 
@@ -54,7 +59,6 @@ use std::fmt;
 impl StructureElementKind {
 	pub fn writeString(&self, out : &mut fmt::Write) -> fmt::Result {
 		match *self {
-			StructureElementKind::Var => out.write_str("Var"),
 			StructureElementKind::Function => out.write_str("Function"),
 			StructureElementKind::Struct => out.write_str("Struct"),
 			StructureElementKind::Impl => out.write_str("Impl"),
@@ -65,6 +69,12 @@ impl StructureElementKind {
 			StructureElementKind::Mod => out.write_str("Mod"),
 			StructureElementKind::Use => out.write_str("Use"),
 			StructureElementKind::TypeAlias => out.write_str("TypeAlias"),
+			StructureElementKind::Const => out.write_str("Const"),
+			StructureElementKind::Static => out.write_str("Static"),
+			StructureElementKind::Macro => out.write_str("Macro"),
+			StructureElementKind::Field => out.write_str("Field"),
+			StructureElementKind::Parameter => out.write_str("Parameter"),
+			StructureElementKind::Method => out.write_str("Method"),
 		}
 	}
 }
@@ -72,67 +82,158 @@ impl StructureElementKind {
 
 /* -----------------  ----------------- */
 
+/// Which serialization `parse_analysis` should produce.
+#[derive(Clone, Copy)]
+pub enum OutputFormat {
+	/// The bespoke `RUST_PARSE_DESCRIBE 0.1 { ... }` token stream.
+	Legacy,
+	/// A single JSON document, easier for modern editor plugins to consume.
+	Json,
+}
+
 pub fn parse_analysis_forStdout(source : &str) {
-	parse_analysis(source, StdoutWrite(io::stdout())).ok();
+	parse_analysis(source, OutputFormat::Legacy, &[], StdoutWrite(io::stdout())).ok();
 	println!("");
 	io::stdout().flush().ok();
 }
 
 
 use ::structure_visitor::StructureVisitor;
+use ::analysis_writer::AnalysisWriter;
+use ::json_writer::JsonWriter;
+use ::locale::{ LangId, catalogs_for_locales, resolve_message };
 
-pub fn parse_analysis<T : fmt::Write + 'static>(source : &str, out : T) -> Result<T> {
+pub fn parse_analysis<T : fmt::Write + 'static>(source : &str, format : OutputFormat, locales : &[LangId], out : T) -> Result<T> {
 	let outRc = Rc::new(RefCell::new(out));
-	try!(parse_analysis_do(source, outRc.clone()));
+	try!(parse_analysis_do(source, format, locales, outRc.clone()));
 	let res = unwrapRcRefCell(outRc);
 	return Ok(res);
 }
 
-pub fn parse_analysis_do(source : &str, out : Rc<RefCell<fmt::Write>>) -> Void {
-	
-	let tokenWriter = TokenWriter { out : out };
-	let tokenWriterRc : Rc<RefCell<TokenWriter>> = Rc::new(RefCell::new(tokenWriter));
-	
-	try!(tokenWriterRc.borrow_mut().writeRaw("RUST_PARSE_DESCRIBE 0.1 {\n"));
-	try!(parse_analysis_contents(source, tokenWriterRc.clone()));
-	try!(tokenWriterRc.borrow_mut().writeRaw("\n}"));
-	
+pub fn parse_analysis_do(source : &str, format : OutputFormat, locales : &[LangId], out : Rc<RefCell<fmt::Write>>) -> Void {
+	match format {
+		OutputFormat::Legacy => parse_analysis_withWriter(source, locales, TokenWriter { out : out }),
+		OutputFormat::Json => parse_analysis_withWriter(source, locales, JsonWriter::new(out)),
+	}
+}
+
+fn parse_analysis_withWriter<W : AnalysisWriter>(source : &str, locales : &[LangId], writer : W) -> Void {
+	let writerRc = Rc::new(RefCell::new(writer));
+
+	try!(writerRc.borrow_mut().write_preamble());
+	try!(parse_analysis_contents(source, locales, writerRc.clone()));
+	try!(writerRc.borrow_mut().write_postamble());
+
 	Ok(())
 }
 
-pub fn parse_analysis_contents(source : &str, tokenWriterRc : Rc<RefCell<TokenWriter>>) -> Void {
-	
+pub fn parse_analysis_contents<W : AnalysisWriter>(source : &str, locales : &[LangId], writerRc : Rc<RefCell<W>>) -> Void {
+
 	let fileLoader = Box::new(DummyFileLoader::new());
 	let codemap = Rc::new(CodeMap::with_file_loader(fileLoader));
-	
+
 	let myEmitter = MessagesHandler::new(codemap.clone());
 	let messages = myEmitter.messages.clone();
 	let handler = Handler::with_emitter(true, true , Box::new(myEmitter));
 	let sess = ParseSess::with_span_handler(handler, codemap.clone());
-	
-	let krate_result = parse_crate(source, &sess);
-	
-	try!(tokenWriterRc.borrow_mut().writeRaw("MESSAGES {\n"));
-	for msg in &messages.lock().unwrap() as &Vec<SourceMessage> {
-		try!(output_message(&mut tokenWriterRc.borrow_mut(), msg.sourcerange, &msg.message, &msg.status_level));
+
+	let krate = parse_crate(source, &sess);
+
+	analysis_finish(&codemap, &messages, locales, &krate, writerRc)
+}
+
+/* -----------------  Whole-crate (multi-file) analysis  ----------------- */
+
+use ::file_loader::FilesystemFileLoader;
+
+/// Analyzes a whole crate rooted at `root` (its `lib.rs` or `main.rs`),
+/// following `mod name;` declarations onto the real filesystem, and
+/// writes the result to `out` in the given format.
+pub fn parse_analysis_path<T : fmt::Write + 'static>(root : &Path, format : OutputFormat, locales : &[LangId], out : T) -> Result<T> {
+	let outRc = Rc::new(RefCell::new(out));
+	try!(parse_analysis_path_do(root, format, locales, outRc.clone()));
+	let res = unwrapRcRefCell(outRc);
+	return Ok(res);
+}
+
+pub fn parse_analysis_path_do(root : &Path, format : OutputFormat, locales : &[LangId], out : Rc<RefCell<fmt::Write>>) -> Void {
+	match format {
+		OutputFormat::Legacy => parse_analysis_path_withWriter(root, locales, TokenWriter { out : out }),
+		OutputFormat::Json => parse_analysis_path_withWriter(root, locales, JsonWriter::new(out)),
 	}
-	try!(tokenWriterRc.borrow_mut().writeRaw("}"));
-	
-	let mut tokenWriter = tokenWriterRc.borrow_mut();
-	
-	match krate_result {
-		Err(_err) => {
-			// Error messages should have been written to out
-		}
-		Ok(ref krate) => { 
-			let mut visitor : StructureVisitor = StructureVisitor::new(&codemap, &mut tokenWriter);  
-			visit::walk_crate(&mut visitor, &krate);
-		}
-	};
-	
+}
+
+fn parse_analysis_path_withWriter<W : AnalysisWriter>(root : &Path, locales : &[LangId], writer : W) -> Void {
+	let writerRc = Rc::new(RefCell::new(writer));
+
+	try!(writerRc.borrow_mut().write_preamble());
+	try!(parse_analysis_contents_path(root, locales, writerRc.clone()));
+	try!(writerRc.borrow_mut().write_postamble());
+
+	Ok(())
+}
+
+pub fn parse_analysis_contents_path<W : AnalysisWriter>(root : &Path, locales : &[LangId], writerRc : Rc<RefCell<W>>) -> Void {
+
+	// Canonicalized so that the entry file we hand the parser is absolute:
+	// the parser then computes `mod name;` candidate paths as absolute too,
+	// so `FilesystemFileLoader::resolve` never has to (and never mistakenly
+	// double-prefixes them by) re-joining them with `root` itself.
+	let root = root.canonicalize().unwrap_or_else(|_| root.to_path_buf());
+	let root = root.as_path();
+
+	let fileLoader = Box::new(FilesystemFileLoader::new(root));
+	let codemap = Rc::new(CodeMap::with_file_loader(fileLoader));
+
+	let myEmitter = MessagesHandler::new(codemap.clone());
+	let messages = myEmitter.messages.clone();
+	let handler = Handler::with_emitter(true, true , Box::new(myEmitter));
+	let sess = ParseSess::with_span_handler(handler, codemap.clone());
+
+	let krate = parse_crate_path(root, &sess);
+
+	analysis_finish(&codemap, &messages, locales, &krate, writerRc)
+}
+
+/// Writes out `messages` and then the structure of `krate`. `krate` may be
+/// a partial crate (see `parse_crate`/`parse_crate_path`) when a syntax
+/// error was hit - in that case it simply describes a prefix of the file,
+/// rather than nothing at all.
+fn analysis_finish<W : AnalysisWriter>(
+	codemap : &Rc<CodeMap>, messages : &Arc<Mutex<Vec<SourceMessage>>>, locales : &[LangId],
+	krate : &ast::Crate, writerRc : Rc<RefCell<W>>) -> Void
+{
+	let catalogs = catalogs_for_locales(locales);
+	let mut messages = messages.lock().unwrap();
+	for msg in messages.iter_mut() {
+		msg.message = resolve_message(&catalogs, &msg.code, &msg.args, &msg.message);
+	}
+
+	try!(writerRc.borrow_mut().write_messages(&messages));
+
+	let mut writer = writerRc.borrow_mut();
+
+	let mut visitor = StructureVisitor::new(codemap, &mut *writer);
+	visit::walk_crate(&mut visitor, krate);
+
 	Ok(())
 }
 
+/// The entry file of the crate rooted at `root`: its `lib.rs` if present,
+/// otherwise its `main.rs`.
+fn crate_entry_file(root : &Path) -> ::std::path::PathBuf {
+	let libRs = root.join("lib.rs");
+	if libRs.is_file() { libRs } else { root.join("main.rs") }
+}
+
+pub fn parse_crate_path<'a>(root : &Path, sess : &'a ParseSess) -> ast::Crate {
+	let cfg = vec![];
+	let entryFile = crate_entry_file(root);
+
+	let parser = parse::new_parser_from_file(sess, cfg, &entryFile);
+	parse_crate_items(parser)
+}
+
 
 /* -----------------  ----------------- */
 
@@ -160,12 +261,50 @@ impl codemap::FileLoader for DummyFileLoader {
     }
 }
 
-pub fn parse_crate<'a>(source : &str, sess : &'a ParseSess) -> parse::PResult<'a, ast::Crate> 
-{
+/// Parses `source` into a best-effort `ast::Crate`: items are parsed one
+/// at a time, so that a fatal syntax error only stops further parsing
+/// instead of discarding everything already parsed. The error itself
+/// (if any) has already been recorded by `sess`'s emitter by the time
+/// this returns, so the caller can always go on to run `StructureVisitor`
+/// over the partial result.
+pub fn parse_crate<'a>(source : &str, sess : &'a ParseSess) -> ast::Crate {
 	let cfg = vec![];
 	let krateName = "_file_module_".to_string();
-	
-	return parse::new_parser_from_source_str(&sess, cfg, krateName, source.to_string()).parse_crate_mod();
+
+	let parser = parse::new_parser_from_source_str(sess, cfg, krateName, source.to_string());
+	parse_crate_items(parser)
+}
+
+fn parse_crate_items(mut parser : parse::parser::Parser) -> ast::Crate {
+	let attrs = match parser.parse_inner_attributes() {
+		Ok(attrs) => attrs,
+		Err(mut diagnostic) => { diagnostic.emit(); vec![] }
+	};
+
+	let mut items = vec![];
+
+	loop {
+		match parser.parse_item() {
+			Ok(Some(item)) => items.push(item),
+			Ok(None) => {
+				if parser.token != parse::token::Eof {
+					let tokenStr = parser.this_token_to_string();
+					let mut diagnostic = parser.fatal(&format!("expected item, found `{}`", tokenStr));
+					diagnostic.emit();
+				}
+				break;
+			}
+			Err(mut diagnostic) => { diagnostic.emit(); break; }
+		}
+	}
+
+	ast::Crate {
+		module : ast::Mod { inner : codemap::DUMMY_SP, items : items },
+		attrs : attrs,
+		config : vec![],
+		span : codemap::DUMMY_SP,
+		exported_macros : vec![],
+	}
 }
 
 
@@ -186,46 +325,116 @@ impl MessagesHandler {
 	}
 	
 	fn writeMessage_handled(&mut self, sourcerange : Option<SourceRange>, msg: &str, lvl: StatusLevel) {
-		
-		let msg = SourceMessage{ status_level : lvl , sourcerange : sourcerange,  message : String::from(msg) };
-		
+
+		let msg = SourceMessage::new(lvl, sourcerange, String::from(msg));
+
 		let mut messages = self.messages.lock().unwrap();
-		
+
 		messages.push(msg);
-		
+
+	}
+
+	/// Attaches `note` to the most-recently-pushed message, or - if there is
+	/// none yet - pushes a new standalone OK-level message for it, so that a
+	/// Help/Note emitted before any primary diagnostic is not lost.
+	fn addNote_toLastMessage(&mut self, note: &str) {
+
+		let mut messages = self.messages.lock().unwrap();
+
+		match messages.last_mut() {
+			Some(lastMessage) => lastMessage.notes.push(String::from(note)),
+			None => messages.push(SourceMessage::new(StatusLevel::OK, None, String::from(note))),
+		}
+
+	}
+
+	/// Attaches a labelled secondary span to the most-recently-pushed
+	/// message, or - if there is none yet - pushes a new standalone OK-level
+	/// message carrying the span and label, so the label text is not lost.
+	fn addRelated_toLastMessage(&mut self, span: Span, label: &str) {
+
+		let sourcerange = SourceRange::new(&self.codemap, span);
+		let mut messages = self.messages.lock().unwrap();
+
+		match messages.last_mut() {
+			Some(lastMessage) => lastMessage.related.push(RelatedSpan { sourcerange : sourcerange, label : String::from(label) }),
+			None => messages.push(SourceMessage::new(StatusLevel::OK, Some(sourcerange), String::from(label))),
+		}
+
+	}
+
+}
+
+/// Pulls the underlying `Span` out of a `RenderSpan`, if it carries one
+/// directly - `Suggestion` doesn't, so it has no span of its own to report.
+fn render_span_to_span(renderSpan : &RenderSpan) -> Option<Span> {
+	match *renderSpan {
+		RenderSpan::FullSpan(span) | RenderSpan::EndSpan(span) | RenderSpan::FileLine(span) => Some(span),
+		RenderSpan::Suggestion(..) => None,
 	}
-	
 }
 
 impl emitter::Emitter for MessagesHandler {
-	
+
     fn emit(&mut self, cmsp: Option<Span>, msg: &str, code: Option<&str>, lvl: Level) {
-    	
-    	match code {
-    		None => {}
-    		Some(code) => {
-    			io::stderr().write_fmt(format_args!("Code: {}\n", code)).unwrap();
-    			panic!("What is code: Option<&str>??");
-			}
-    	}
-    	
-    	
+
 		let sourcerange = match cmsp {
 			Some(span) => Some(SourceRange::new(&self.codemap, span)),
 			None => None,
 		};
-		
-		self.writeMessage_handled(sourcerange, msg, level_to_status_level(lvl));
+
+		let mut message = SourceMessage::new(level_to_status_level(lvl), sourcerange, String::from(msg));
+		message.code = code.map(String::from);
+		message.args = extract_args(code, msg);
+
+		self.messages.lock().unwrap().push(message);
     }
-    
-    fn custom_emit(&mut self, _: RenderSpan, msg: &str, lvl: Level) {
+
+    fn custom_emit(&mut self, renderSpan: RenderSpan, msg: &str, lvl: Level) {
+    	let span = render_span_to_span(&renderSpan);
+
     	if match lvl { Level::Help | Level::Note => true, _ => false } {
+    		match span {
+    			Some(span) => self.addRelated_toLastMessage(span, msg),
+    			None => self.addNote_toLastMessage(msg),
+    		}
     		return;
     	}
-    	
-    	self.writeMessage_handled(None, msg, level_to_status_level(lvl));
+
+    	let sourcerange = span.map(|span| SourceRange::new(&self.codemap, span));
+    	self.writeMessage_handled(sourcerange, msg, level_to_status_level(lvl));
     }
-	
+
+}
+
+/// Pulls the named arguments a catalog template might interpolate out of
+/// rustc's already-formatted message text, keyed by the diagnostic's
+/// stable `code`. Empty for any diagnostic we don't specifically know how
+/// to pick apart.
+fn extract_args(code: Option<&str>, msg: &str) -> Vec<(String, String)> {
+	match code {
+		Some("E0308") => extract_expected_found(msg),
+		_ => vec![],
+	}
+}
+
+/// Pulls `expected`/`found` out of rustc's "expected `X`, found `Y`"
+/// phrasing for E0308 mismatched-type messages.
+fn extract_expected_found(msg: &str) -> Vec<(String, String)> {
+	let quoted : Vec<&str> = msg.split('`')
+		.enumerate()
+		.filter(|&(ix, _)| ix % 2 == 1)
+		.map(|(_, part)| part)
+		.collect();
+
+	if quoted.len() >= 2 {
+		vec![
+			(String::from("expected"), String::from(quoted[0])),
+			(String::from("found"), String::from(quoted[1])),
+		]
+	} else {
+		vec![]
+	}
 }
 
 fn level_to_status_level(lvl: Level) -> StatusLevel {
@@ -242,53 +451,27 @@ fn level_to_status_level(lvl: Level) -> StatusLevel {
 impl MessagesHandler {
 }
 
+#[cfg(test)]
+mod tests {
+	use super::*;
 
-/* -----------------  ----------------- */
-
-fn output_message(tokenWriter: &mut TokenWriter, opt_sr : Option<SourceRange>, msg: & str, lvl: &StatusLevel) 
-	-> Void
-{
-	
-	try!(tokenWriter.out.borrow_mut().write_str("MESSAGE { "));
-	
-	try!(outputString_Level(&lvl, tokenWriter));
-	
-	try!(outputString_optSourceRange(&opt_sr, tokenWriter));
-	
-	try!(tokenWriter.writeStringToken(msg));
-	
-	try!(tokenWriter.out.borrow_mut().write_str("}\n"));
-	
-	Ok(())
-}
-
-
-pub fn outputString_Level(lvl : &StatusLevel, writer : &mut TokenWriter) -> Void {
-	
-	try!(lvl.output_string(&mut *writer.out.borrow_mut()));
-	try!(writer.writeRaw(" "));
-	
-	Ok(())
-}
+	fn parse(source : &str) -> ast::Crate {
+		let codemap = Rc::new(CodeMap::with_file_loader(Box::new(DummyFileLoader::new())));
+		let handler = Handler::with_emitter(true, true, Box::new(MessagesHandler::new(codemap.clone())));
+		let sess = ParseSess::with_span_handler(handler, codemap);
+		parse_crate(source, &sess)
+	}
 
-pub fn outputString_SourceRange(sr : &SourceRange, writer : &mut TokenWriter) -> Void {
-	let mut out = writer.out.borrow_mut(); 
-	try!(out.write_fmt(format_args!("{{ {} {} {} {} }}", 
-		sr.start_pos.line, sr.start_pos.col.0,
-		sr.end_pos.line, sr.end_pos.col.0,
-	)));
-	
-	Ok(())
-}
+	#[test]
+	fn leading_inner_attribute_is_consumed_before_items() {
+		let krate = parse("#![allow(dead_code)]\nfn foo() {}\n");
+		assert_eq!(krate.attrs.len(), 1);
+		assert_eq!(krate.module.items.len(), 1);
+	}
 
-pub fn outputString_optSourceRange(sr : &Option<SourceRange>, writer : &mut TokenWriter) -> Void {
-	
-	match sr {
-		&None => try!(writer.out.borrow_mut().write_str("{ }")) ,
-		&Some(ref sr) => try!(outputString_SourceRange(sr, writer)) ,
+	#[test]
+	fn syntax_error_still_yields_the_items_parsed_before_it() {
+		let krate = parse("fn foo() {}\nfn bar(\n");
+		assert_eq!(krate.module.items.len(), 1);
 	}
-	
-	try!(writer.out.borrow_mut().write_str(" "));
-	
-	Ok(())
 }
@@ -0,0 +1,113 @@
+// Copyright 2015 Bruno Medeiros
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fmt;
+
+use ::syntex_syntax::syntax::codemap::{ CodeMap, Span };
+
+/* -----------------  SourceRange  ----------------- */
+
+/// A line/column position in some source file.
+#[derive(Clone, Copy, Debug)]
+pub struct SourcePos {
+	pub line : usize,
+	pub col : CharCol,
+}
+
+/// A zero-based character offset within a line.
+#[derive(Clone, Copy, Debug)]
+pub struct CharCol(pub usize);
+
+/// A range in some source file, given as a pair of line/column positions.
+/// `file` is the path of the originating file, as seen by the `CodeMap` -
+/// for a multi-file crate this may differ between messages/elements that
+/// come from different modules.
+#[derive(Clone, Debug)]
+pub struct SourceRange {
+	pub file : String,
+	pub start_pos : SourcePos,
+	pub end_pos : SourcePos,
+}
+
+impl SourceRange {
+	pub fn new(codemap : &CodeMap, span : Span) -> SourceRange {
+		let lo = codemap.lookup_char_pos(span.lo);
+		let hi = codemap.lookup_char_pos(span.hi);
+
+		SourceRange {
+			file : lo.file.name.clone(),
+			start_pos : SourcePos { line : lo.line, col : CharCol(lo.col.0) },
+			end_pos : SourcePos { line : hi.line, col : CharCol(hi.col.0) },
+		}
+	}
+}
+
+/* -----------------  StatusLevel  ----------------- */
+
+pub enum StatusLevel {
+	OK,
+	WARNING,
+	ERROR,
+}
+
+impl StatusLevel {
+	pub fn output_string(&self, out : &mut fmt::Write) -> fmt::Result {
+		match *self {
+			StatusLevel::OK => out.write_str("OK"),
+			StatusLevel::WARNING => out.write_str("WARNING"),
+			StatusLevel::ERROR => out.write_str("ERROR"),
+		}
+	}
+}
+
+/* -----------------  SourceMessage  ----------------- */
+
+/// A secondary source range attached to a message, with a short label
+/// describing its relevance (mirrors rustc's "related" spans).
+pub struct RelatedSpan {
+	pub sourcerange : SourceRange,
+	pub label : String,
+}
+
+/// A diagnostic message, enriched with the pieces that rustc itself
+/// produces but that were previously being thrown away: an optional
+/// error code (e.g. "E0308"), secondary help/note strings, and related
+/// labelled spans.
+pub struct SourceMessage {
+	pub status_level : StatusLevel,
+	pub sourcerange : Option<SourceRange>,
+	pub message : String,
+	pub code : Option<String>,
+	pub notes : Vec<String>,
+	pub related : Vec<RelatedSpan>,
+	/// Named arguments for localizing `message`, keyed for interpolation
+	/// into a catalog template resolved via `code`. Empty unless the
+	/// diagnostic is one we specifically recognize - rustc hands us
+	/// already-interpolated text, not structured arguments.
+	pub args : Vec<(String, String)>,
+}
+
+impl SourceMessage {
+	pub fn new(status_level : StatusLevel, sourcerange : Option<SourceRange>, message : String) -> SourceMessage {
+		SourceMessage {
+			status_level : status_level,
+			sourcerange : sourcerange,
+			message : message,
+			code : None,
+			notes : vec![],
+			related : vec![],
+			args : vec![],
+		}
+	}
+}
@@ -0,0 +1,169 @@
+// Copyright 2015 Bruno Medeiros
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use ::util::core::*;
+use ::source_model::SourceRange;
+use ::analysis_writer::AnalysisWriter;
+use ::parse_describe::StructureElementKind;
+
+use ::syntex_syntax::syntax::ast;
+use ::syntex_syntax::visit;
+use ::syntex_syntax::codemap::{ CodeMap, Span };
+
+/// Walks a parsed crate and emits one structure element per top-level
+/// item - and, within it, one per field/parameter/method - so an editor
+/// can build a nested outline/breadcrumb view. Generic over the
+/// `AnalysisWriter` so it works with either the legacy token stream or
+/// the JSON writer.
+pub struct StructureVisitor<'a, 'cm, W : AnalysisWriter + 'a> {
+	codemap : &'cm CodeMap,
+	writer : &'a mut W,
+}
+
+impl<'a, 'cm, W : AnalysisWriter + 'a> StructureVisitor<'a, 'cm, W> {
+
+	pub fn new(codemap : &'cm CodeMap, writer : &'a mut W) -> StructureVisitor<'a, 'cm, W> {
+		StructureVisitor { codemap : codemap, writer : writer }
+	}
+
+	fn sourceRange(&self, span : Span) -> SourceRange {
+		SourceRange::new(self.codemap, span)
+	}
+
+	fn writeLeafElement(&mut self, kind : StructureElementKind, span : Span, name : &str) {
+		let sourcerange = self.sourceRange(span);
+		self.writer.begin_element(&kind, &sourcerange, name).ok();
+		self.writer.end_element().ok();
+	}
+
+	fn writeFields(&mut self, fields : &[ast::StructField]) {
+		for field in fields {
+			let name = field.ident.map(|id| id.name.as_str().to_string()).unwrap_or_else(|| String::from("_"));
+			self.writeLeafElement(StructureElementKind::Field, field.span, &name);
+		}
+	}
+
+	fn writeParameters(&mut self, inputs : &[ast::Arg]) {
+		for arg in inputs {
+			let name = match arg.pat.node {
+				ast::PatKind::Ident(_, ref ident, _) => ident.node.name.as_str().to_string(),
+				_ => String::from("_"),
+			};
+			self.writeLeafElement(StructureElementKind::Parameter, arg.pat.span, &name);
+		}
+	}
+
+	fn visitTraitItem(&mut self, ti : &ast::TraitItem) {
+		let name = ti.ident.name.as_str().to_string();
+
+		match ti.node {
+			ast::TraitItemKind::Method(ref sig, ref body) => {
+				let sourcerange = self.sourceRange(ti.span);
+				self.writer.begin_element(&StructureElementKind::Method, &sourcerange, &name).ok();
+				self.writeParameters(&sig.decl.inputs);
+				if let Some(ref block) = *body {
+					visit::walk_block(self, block);
+				}
+				self.writer.end_element().ok();
+			}
+			ast::TraitItemKind::Const(..) => self.writeLeafElement(StructureElementKind::Const, ti.span, &name),
+			ast::TraitItemKind::Type(..) => self.writeLeafElement(StructureElementKind::TypeAlias, ti.span, &name),
+			_ => {}
+		}
+	}
+
+	fn visitImplItem(&mut self, ii : &ast::ImplItem) {
+		let name = ii.ident.name.as_str().to_string();
+
+		match ii.node {
+			ast::ImplItemKind::Method(ref sig, ref body) => {
+				let sourcerange = self.sourceRange(ii.span);
+				self.writer.begin_element(&StructureElementKind::Method, &sourcerange, &name).ok();
+				self.writeParameters(&sig.decl.inputs);
+				visit::walk_block(self, body);
+				self.writer.end_element().ok();
+			}
+			ast::ImplItemKind::Const(..) => self.writeLeafElement(StructureElementKind::Const, ii.span, &name),
+			ast::ImplItemKind::Type(..) => self.writeLeafElement(StructureElementKind::TypeAlias, ii.span, &name),
+			_ => {}
+		}
+	}
+
+}
+
+impl<'a, 'cm, 'v, W : AnalysisWriter + 'a> visit::Visitor<'v> for StructureVisitor<'a, 'cm, W> {
+
+	fn visit_item(&mut self, item : &'v ast::Item) {
+		let name = item.ident.name.as_str().to_string();
+
+		match item.node {
+			ast::ItemKind::Fn(ref decl, ..) => {
+				let sourcerange = self.sourceRange(item.span);
+				self.writer.begin_element(&StructureElementKind::Function, &sourcerange, &name).ok();
+				self.writeParameters(&decl.inputs);
+				visit::walk_item(self, item);
+				self.writer.end_element().ok();
+			}
+			ast::ItemKind::Struct(ref variantData, _) => {
+				let sourcerange = self.sourceRange(item.span);
+				self.writer.begin_element(&StructureElementKind::Struct, &sourcerange, &name).ok();
+				self.writeFields(variantData.fields());
+				self.writer.end_element().ok();
+			}
+			ast::ItemKind::Enum(ref enumDef, _) => {
+				let sourcerange = self.sourceRange(item.span);
+				self.writer.begin_element(&StructureElementKind::Enum, &sourcerange, &name).ok();
+				for variant in &enumDef.variants {
+					let variantName = variant.node.name.name.as_str().to_string();
+					let variantRange = self.sourceRange(variant.span);
+					self.writer.begin_element(&StructureElementKind::EnumVariant, &variantRange, &variantName).ok();
+					self.writeFields(variant.node.data.fields());
+					self.writer.end_element().ok();
+				}
+				self.writer.end_element().ok();
+			}
+			ast::ItemKind::Trait(.., ref items) => {
+				let sourcerange = self.sourceRange(item.span);
+				self.writer.begin_element(&StructureElementKind::Trait, &sourcerange, &name).ok();
+				for traitItem in items {
+					self.visitTraitItem(traitItem);
+				}
+				self.writer.end_element().ok();
+			}
+			ast::ItemKind::Impl(.., ref items) => {
+				let sourcerange = self.sourceRange(item.span);
+				self.writer.begin_element(&StructureElementKind::Impl, &sourcerange, &name).ok();
+				for implItem in items {
+					self.visitImplItem(implItem);
+				}
+				self.writer.end_element().ok();
+			}
+			ast::ItemKind::Mod(_) => {
+				let sourcerange = self.sourceRange(item.span);
+				self.writer.begin_element(&StructureElementKind::Mod, &sourcerange, &name).ok();
+				visit::walk_item(self, item);
+				self.writer.end_element().ok();
+			}
+			ast::ItemKind::Use(_) => self.writeLeafElement(StructureElementKind::Use, item.span, &name),
+			ast::ItemKind::ExternCrate(_) => self.writeLeafElement(StructureElementKind::ExternCrate, item.span, &name),
+			ast::ItemKind::Ty(..) => self.writeLeafElement(StructureElementKind::TypeAlias, item.span, &name),
+			ast::ItemKind::Static(..) => self.writeLeafElement(StructureElementKind::Static, item.span, &name),
+			ast::ItemKind::Const(..) => self.writeLeafElement(StructureElementKind::Const, item.span, &name),
+			ast::ItemKind::MacroDef(..) => self.writeLeafElement(StructureElementKind::Macro, item.span, &name),
+			ast::ItemKind::Mac(..) => self.writeLeafElement(StructureElementKind::Macro, item.span, &name),
+			_ => visit::walk_item(self, item),
+		}
+	}
+
+}
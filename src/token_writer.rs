@@ -0,0 +1,173 @@
+// Copyright 2015 Bruno Medeiros
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use ::util::core::*;
+use ::source_model::{ SourceMessage, SourceRange, StatusLevel, RelatedSpan };
+use ::parse_describe::StructureElementKind;
+use ::analysis_writer::AnalysisWriter;
+
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::fmt;
+
+/// Writes the bespoke `RUST_PARSE_DESCRIBE` token stream: a hand-rolled
+/// grammar of `{ }` delimited groups and quoted string tokens.
+pub struct TokenWriter {
+	pub out : Rc<RefCell<fmt::Write>>,
+}
+
+impl TokenWriter {
+
+	pub fn writeRaw(&mut self, str : &str) -> Void {
+		try!(self.out.borrow_mut().write_str(str));
+		Ok(())
+	}
+
+	/// Writes `str` as a quoted string token, escaping `"` and `\`.
+	pub fn writeStringToken(&mut self, str : &str) -> Void {
+		let mut out = self.out.borrow_mut();
+
+		try!(out.write_str("\""));
+		for ch in str.chars() {
+			match ch {
+				'"' => try!(out.write_str("\\\"")),
+				'\\' => try!(out.write_str("\\\\")),
+				_ => try!(out.write_char(ch)),
+			}
+		}
+		try!(out.write_str("\" "));
+
+		Ok(())
+	}
+
+}
+
+impl AnalysisWriter for TokenWriter {
+
+	fn write_preamble(&mut self) -> Void {
+		try!(self.writeRaw("RUST_PARSE_DESCRIBE 0.1 {\n"));
+		Ok(())
+	}
+
+	fn write_messages(&mut self, messages : &[SourceMessage]) -> Void {
+		try!(self.writeRaw("MESSAGES {\n"));
+		for msg in messages {
+			try!(output_message(self, msg));
+		}
+		try!(self.writeRaw("}"));
+		Ok(())
+	}
+
+	fn begin_element(&mut self, kind : &StructureElementKind, range : &SourceRange, name : &str) -> Void {
+		try!(self.writeRaw("ELEMENT { "));
+		try!(kind.writeString(&mut *self.out.borrow_mut()));
+		try!(self.writeRaw(" "));
+		try!(outputString_SourceRange(range, self));
+		try!(self.writeStringToken(name));
+		try!(self.writeRaw("CHILDREN {\n"));
+		Ok(())
+	}
+
+	fn end_element(&mut self) -> Void {
+		try!(self.writeRaw("} }\n"));
+		Ok(())
+	}
+
+	fn write_postamble(&mut self) -> Void {
+		try!(self.writeRaw("\n}"));
+		Ok(())
+	}
+
+}
+
+fn output_message(tokenWriter : &mut TokenWriter, msg : &SourceMessage) -> Void {
+
+	try!(tokenWriter.out.borrow_mut().write_str("MESSAGE { "));
+
+	try!(outputString_Level(&msg.status_level, tokenWriter));
+
+	try!(outputString_optSourceRange(&msg.sourcerange, tokenWriter));
+
+	try!(tokenWriter.writeStringToken(&msg.message));
+
+	try!(outputString_code(&msg.code, tokenWriter));
+	try!(outputString_notes(&msg.notes, tokenWriter));
+	try!(outputString_related(&msg.related, tokenWriter));
+
+	try!(tokenWriter.out.borrow_mut().write_str("}\n"));
+
+	Ok(())
+}
+
+fn outputString_code(code : &Option<String>, writer : &mut TokenWriter) -> Void {
+	try!(writer.writeRaw("CODE "));
+	match code {
+		&None => try!(writer.writeStringToken("")),
+		&Some(ref code) => try!(writer.writeStringToken(code)),
+	}
+	Ok(())
+}
+
+fn outputString_notes(notes : &Vec<String>, writer : &mut TokenWriter) -> Void {
+	try!(writer.writeRaw("NOTES { "));
+	for note in notes {
+		try!(writer.writeStringToken(note));
+	}
+	try!(writer.writeRaw("} "));
+	Ok(())
+}
+
+fn outputString_related(related : &Vec<RelatedSpan>, writer : &mut TokenWriter) -> Void {
+	try!(writer.writeRaw("RELATED { "));
+	for rel in related {
+		try!(writer.writeRaw("{ "));
+		try!(outputString_SourceRange(&rel.sourcerange, writer));
+		try!(writer.writeStringToken(&rel.label));
+		try!(writer.writeRaw("} "));
+	}
+	try!(writer.writeRaw("} "));
+	Ok(())
+}
+
+pub fn outputString_Level(lvl : &StatusLevel, writer : &mut TokenWriter) -> Void {
+
+	try!(lvl.output_string(&mut *writer.out.borrow_mut()));
+	try!(writer.writeRaw(" "));
+
+	Ok(())
+}
+
+pub fn outputString_SourceRange(sr : &SourceRange, writer : &mut TokenWriter) -> Void {
+	try!(writer.writeStringToken(&sr.file));
+
+	let mut out = writer.out.borrow_mut();
+	try!(out.write_fmt(format_args!("{{ {} {} {} {} }}",
+		sr.start_pos.line, sr.start_pos.col.0,
+		sr.end_pos.line, sr.end_pos.col.0,
+	)));
+
+	Ok(())
+}
+
+pub fn outputString_optSourceRange(sr : &Option<SourceRange>, writer : &mut TokenWriter) -> Void {
+
+	match sr {
+		&None => try!(writer.out.borrow_mut().write_str("{ }")) ,
+		&Some(ref sr) => try!(outputString_SourceRange(sr, writer)) ,
+	}
+
+	try!(writer.out.borrow_mut().write_str(" "));
+
+	Ok(())
+}
@@ -0,0 +1,54 @@
+// Copyright 2015 Bruno Medeiros
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::fmt;
+use std::io;
+
+/// A catch-all error used for the plumbing glue in this crate (writing to
+/// the output sink is the only thing that can really fail here).
+#[derive(Debug)]
+pub struct GError {
+	pub message : String,
+}
+
+impl fmt::Display for GError {
+	fn fmt(&self, fmtter : &mut fmt::Formatter) -> fmt::Result {
+		fmtter.write_str(&self.message)
+	}
+}
+
+impl From<fmt::Error> for GError {
+	fn from(err : fmt::Error) -> GError {
+		GError { message : format!("{}", err) }
+	}
+}
+
+impl From<io::Error> for GError {
+	fn from(err : io::Error) -> GError {
+		GError { message : format!("{}", err) }
+	}
+}
+
+pub type Result<T> = ::std::result::Result<T, GError>;
+pub type Void = Result<()>;
+
+/// Unwraps an `Rc<RefCell<T>>` that is known to have only one owner left.
+pub fn unwrapRcRefCell<T>(rc : Rc<RefCell<T>>) -> T {
+	match Rc::try_unwrap(rc) {
+		Ok(refCell) => refCell.into_inner(),
+		Err(_) => panic!("unwrapRcRefCell: Rc has more than one strong reference"),
+	}
+}
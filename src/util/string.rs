@@ -0,0 +1,25 @@
+// Copyright 2015 Bruno Medeiros
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fmt;
+use std::io;
+
+/// Adapts an `io::Write` so it can be used where an `fmt::Write` is expected.
+pub struct StdoutWrite<T : io::Write>(pub T);
+
+impl<T : io::Write> fmt::Write for StdoutWrite<T> {
+	fn write_str(&mut self, s : &str) -> fmt::Result {
+		self.0.write_all(s.as_bytes()).map_err(|_| fmt::Error)
+	}
+}